@@ -1,9 +1,13 @@
 use anyhow::{Context, Result};
 use flate2::read::GzDecoder;
 use libcontainer::container::builder::ContainerBuilder;
+use libcontainer::container::Container;
+use libcontainer::oci_spec::image::ImageConfiguration;
 use libcontainer::oci_spec::runtime::{
-    LinuxBuilder, LinuxIdMappingBuilder, LinuxNamespace, LinuxNamespaceBuilder, LinuxNamespaceType,
-    Mount, Spec,
+    Arch, IOPriorityClass, LinuxBuilder, LinuxIOPriority, LinuxIOPriorityBuilder,
+    LinuxIdMappingBuilder, LinuxNamespace, LinuxNamespaceBuilder, LinuxNamespaceType, LinuxSeccomp,
+    LinuxSeccompAction, LinuxSeccompBuilder, LinuxSchedulerFlag, LinuxSchedulerPolicy,
+    LinuxSyscallBuilder, Mount, Scheduler, SchedulerBuilder, Spec, UserBuilder,
 };
 use libcontainer::syscall::syscall::SyscallType;
 use libcontainer::workload::{Executor, ExecutorError, ExecutorValidationError};
@@ -20,11 +24,21 @@ use oci_distribution::manifest;
 use oci_distribution::secrets::RegistryAuth;
 use oci_distribution::Reference;
 use serde_json::to_writer_pretty;
+use sha2::{Digest, Sha256};
+use nix::sys::socket::{recvmsg, ControlMessageOwned, MsgFlags};
+use nix::sys::termios::{self, SetArg};
+use nix::sys::uio::IoSliceMut;
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufWriter, Cursor, Write};
+use std::io::{BufWriter, Cursor, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixListener;
 use std::path::Path;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use tar::Archive;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
 use tracing_subscriber::prelude::*;
 
 #[derive(Clone)]
@@ -40,7 +54,159 @@ impl Executor for MyExecutor {
     }
 }
 
-pub fn get_rootless() -> Result<Spec> {
+// The common runc/Docker seccomp profile shape: a default action plus syscall rules.
+#[derive(Debug, serde::Deserialize)]
+struct SeccompProfile {
+    #[serde(rename = "defaultAction", default)]
+    default_action: Option<String>,
+    #[serde(default)]
+    syscalls: Vec<SeccompSyscallRule>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SeccompSyscallRule {
+    names: Vec<String>,
+    #[serde(default)]
+    action: Option<String>,
+}
+
+fn load_seccomp_profile(path: &Path) -> Result<SeccompProfile> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read seccomp profile {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse seccomp profile {}", path.display()))
+}
+
+fn parse_seccomp_action(action: &str) -> Result<LinuxSeccompAction> {
+    Ok(match action {
+        "SCMP_ACT_KILL" => LinuxSeccompAction::ScmpActKill,
+        "SCMP_ACT_KILL_PROCESS" => LinuxSeccompAction::ScmpActKillProcess,
+        "SCMP_ACT_TRAP" => LinuxSeccompAction::ScmpActTrap,
+        "SCMP_ACT_ERRNO" => LinuxSeccompAction::ScmpActErrno,
+        "SCMP_ACT_TRACE" => LinuxSeccompAction::ScmpActTrace,
+        "SCMP_ACT_ALLOW" => LinuxSeccompAction::ScmpActAllow,
+        "SCMP_ACT_LOG" => LinuxSeccompAction::ScmpActLog,
+        other => anyhow::bail!("unknown seccomp action '{other}'"),
+    })
+}
+
+// Map the architecture this binary is running on to its seccomp constant.
+fn native_seccomp_arch() -> Result<Arch> {
+    Ok(match std::env::consts::ARCH {
+        "x86_64" => Arch::ScmpArchX86_64,
+        "x86" => Arch::ScmpArchX86,
+        "aarch64" => Arch::ScmpArchAarch64,
+        "arm" => Arch::ScmpArchArm,
+        other => anyhow::bail!("unsupported architecture '{other}' for seccomp profile"),
+    })
+}
+
+fn build_seccomp(profile: &SeccompProfile) -> Result<LinuxSeccomp> {
+    let default_action = match &profile.default_action {
+        Some(action) => parse_seccomp_action(action)?,
+        None => LinuxSeccompAction::ScmpActErrno,
+    };
+
+    let syscalls = profile
+        .syscalls
+        .iter()
+        .map(|rule| {
+            let action = match &rule.action {
+                Some(action) => parse_seccomp_action(action)?,
+                None => LinuxSeccompAction::ScmpActAllow,
+            };
+            Ok(LinuxSyscallBuilder::default()
+                .names(rule.names.clone())
+                .action(action)
+                .build()?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(LinuxSeccompBuilder::default()
+        .default_action(default_action)
+        .architectures(vec![native_seccomp_arch()?])
+        .syscalls(syscalls)
+        .build()?)
+}
+
+// CLI-surfaced CPU scheduling settings for the container's init process.
+#[derive(Debug, Default)]
+pub struct SchedulerConfig {
+    pub policy: String,
+    pub nice: Option<i32>,
+    pub flags: Vec<String>,
+}
+
+// CLI-surfaced I/O priority settings for the container's init process.
+#[derive(Debug, Default)]
+pub struct IoPriorityConfig {
+    pub class: String,
+    pub priority: i64,
+}
+
+// SCHED_FIFO/SCHED_RR/SCHED_DEADLINE are deliberately not accepted here: they
+// require a real-time priority (and, for DEADLINE, a runtime/deadline/period
+// triple) that this config has no fields for, so `sched_setattr` would reject
+// them at their zero-valued `SchedulerBuilder` default.
+fn parse_scheduler_policy(policy: &str) -> Result<LinuxSchedulerPolicy> {
+    Ok(match policy {
+        "SCHED_OTHER" => LinuxSchedulerPolicy::SchedOther,
+        "SCHED_BATCH" => LinuxSchedulerPolicy::SchedBatch,
+        "SCHED_IDLE" => LinuxSchedulerPolicy::SchedIdle,
+        other => anyhow::bail!("unknown or unsupported scheduler policy '{other}'"),
+    })
+}
+
+fn parse_scheduler_flag(flag: &str) -> Result<LinuxSchedulerFlag> {
+    Ok(match flag {
+        "SCHED_FLAG_RESET_ON_FORK" => LinuxSchedulerFlag::SchedFlagResetOnFork,
+        "SCHED_FLAG_RECLAIM" => LinuxSchedulerFlag::SchedFlagReclaim,
+        "SCHED_FLAG_DL_OVERRUN" => LinuxSchedulerFlag::SchedFlagDlOverrun,
+        "SCHED_FLAG_KEEP_POLICY" => LinuxSchedulerFlag::SchedFlagKeepPolicy,
+        "SCHED_FLAG_KEEP_PARAMS" => LinuxSchedulerFlag::SchedFlagKeepParams,
+        "SCHED_FLAG_UTIL_CLAMP_MIN" => LinuxSchedulerFlag::SchedFlagUtilClampMin,
+        "SCHED_FLAG_UTIL_CLAMP_MAX" => LinuxSchedulerFlag::SchedFlagUtilClampMax,
+        other => anyhow::bail!("unknown scheduler flag '{other}'"),
+    })
+}
+
+fn build_scheduler(config: &SchedulerConfig) -> Result<Scheduler> {
+    let mut builder = SchedulerBuilder::default().policy(parse_scheduler_policy(&config.policy)?);
+    if let Some(nice) = config.nice {
+        builder = builder.nice(nice);
+    }
+    if !config.flags.is_empty() {
+        let flags = config
+            .flags
+            .iter()
+            .map(|flag| parse_scheduler_flag(flag))
+            .collect::<Result<Vec<_>>>()?;
+        builder = builder.flags(flags);
+    }
+    Ok(builder.build()?)
+}
+
+fn parse_io_priority_class(class: &str) -> Result<IOPriorityClass> {
+    Ok(match class {
+        "IOPRIO_CLASS_RT" => IOPriorityClass::IoprioClassRt,
+        "IOPRIO_CLASS_BE" => IOPriorityClass::IoprioClassBe,
+        "IOPRIO_CLASS_IDLE" => IOPriorityClass::IoprioClassIdle,
+        other => anyhow::bail!("unknown I/O priority class '{other}'"),
+    })
+}
+
+fn build_io_priority(config: &IoPriorityConfig) -> Result<LinuxIOPriority> {
+    Ok(LinuxIOPriorityBuilder::default()
+        .class(parse_io_priority_class(&config.class)?)
+        .priority(config.priority)
+        .build()?)
+}
+
+pub fn get_rootless(
+    seccomp_profile: Option<&Path>,
+    scheduler: Option<&SchedulerConfig>,
+    io_priority: Option<&IoPriorityConfig>,
+) -> Result<Spec> {
     // Remove network and user namespace from the default spec
     let mut namespaces: Vec<LinuxNamespace> =
         libcontainer::oci_spec::runtime::get_default_namespaces()
@@ -60,7 +226,7 @@ pub fn get_rootless() -> Result<Spec> {
     let uid = nix::unistd::geteuid().as_raw();
     let gid = nix::unistd::getegid().as_raw();
 
-    let linux = LinuxBuilder::default()
+    let mut linux_builder = LinuxBuilder::default()
         .namespaces(namespaces)
         .uid_mappings(vec![LinuxIdMappingBuilder::default()
             .host_id(uid)
@@ -71,8 +237,14 @@ pub fn get_rootless() -> Result<Spec> {
             .host_id(gid)
             .container_id(0_u32)
             .size(1_u32)
-            .build()?])
-        .build()?;
+            .build()?]);
+
+    if let Some(profile_path) = seccomp_profile {
+        let profile = load_seccomp_profile(profile_path)?;
+        linux_builder = linux_builder.seccomp(build_seccomp(&profile)?);
+    }
+
+    let linux = linux_builder.build()?;
 
     // Prepare the mounts
 
@@ -104,15 +276,172 @@ pub fn get_rootless() -> Result<Spec> {
 
     let mut spec = Spec::default();
     spec.set_linux(Some(linux)).set_mounts(Some(mounts));
+
+    if scheduler.is_some() || io_priority.is_some() {
+        let mut process = spec.process().clone().unwrap_or_default();
+        if let Some(scheduler) = scheduler {
+            process.set_scheduler(Some(build_scheduler(scheduler)?));
+        }
+        if let Some(io_priority) = io_priority {
+            process.set_io_priority(Some(build_io_priority(io_priority)?));
+        }
+        spec.set_process(Some(process));
+    }
+
     Ok(spec)
 }
 
-pub fn spec() -> Result<()> {
+// Merge the pulled image's config (entrypoint, cmd, env, cwd, user) into `spec`,
+// with extra_args/extra_env appended on top of the image's own defaults.
+pub fn apply_image_config(
+    spec: &mut Spec,
+    image_config: &ImageConfiguration,
+    rootfs: &Path,
+    extra_args: &[String],
+    extra_env: &[String],
+) -> Result<()> {
+    let config = image_config.config().clone().unwrap_or_default();
+
+    let mut process = spec.process().clone().unwrap_or_default();
+
+    let mut args = Vec::new();
+    if let Some(entrypoint) = config.entrypoint() {
+        args.extend(entrypoint.iter().cloned());
+    }
+    if let Some(cmd) = config.cmd() {
+        args.extend(cmd.iter().cloned());
+    }
+    args.extend(extra_args.iter().cloned());
+    if !args.is_empty() {
+        process.set_args(Some(args));
+    }
+
+    let mut env = config.env().clone().unwrap_or_default();
+    env.extend(extra_env.iter().cloned());
+    if !env.is_empty() {
+        process.set_env(Some(env));
+    }
+
+    if let Some(working_dir) = config.working_dir() {
+        if !working_dir.is_empty() {
+            process.set_cwd(PathBuf::from(working_dir));
+        }
+    }
+
+    if let Some(user) = config.user() {
+        if !user.is_empty() {
+            process.set_user(resolve_user(user, rootfs)?);
+        }
+    }
+
+    spec.set_process(Some(process));
+    Ok(())
+}
+
+// Resolve an OCI image `config.User` string (`name`, `uid`, `name:group` or `uid:gid`)
+// against the unpacked rootfs's `/etc/passwd` and `/etc/group`, the same lookup a
+// runtime does at exec time.
+fn resolve_user(user: &str, rootfs: &Path) -> Result<libcontainer::oci_spec::runtime::User> {
+    let (name, group) = match user.split_once(':') {
+        Some((u, g)) => (u, Some(g)),
+        None => (user, None),
+    };
+
+    // For a named user, resolving the passwd entry once gives us both the uid
+    // and its primary gid; for a numeric uid we still need one lookup by uid
+    // to find the primary gid, below.
+    let (uid, passwd_gid): (u32, Option<u32>) = match name.parse() {
+        Ok(uid) => (uid, lookup_passwd_gid(rootfs, uid)?),
+        Err(_) => {
+            let (uid, gid) = lookup_passwd_entry(rootfs, name)?
+                .with_context(|| format!("user '{name}' not found in {}/etc/passwd", rootfs.display()))?;
+            (uid, Some(gid))
+        }
+    };
+
+    // With no group given, fall back to the matched passwd entry's primary gid
+    // (field 4) rather than root; a named group that isn't numeric is resolved
+    // against /etc/group instead of silently becoming gid 0.
+    let gid: u32 = match group {
+        Some(g) => match g.parse() {
+            Ok(gid) => gid,
+            Err(_) => lookup_group_gid(rootfs, g)?.with_context(|| {
+                format!("group '{g}' not found in {}/etc/group", rootfs.display())
+            })?,
+        },
+        None => passwd_gid.unwrap_or(0),
+    };
+
+    Ok(UserBuilder::default().uid(uid).gid(gid).build()?)
+}
+
+// Find a passwd entry by name, returning its (uid, gid) fields.
+fn lookup_passwd_entry(rootfs: &Path, name: &str) -> Result<Option<(u32, u32)>> {
+    let contents = match std::fs::read_to_string(rootfs.join("etc/passwd")) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(None),
+    };
+
+    for line in contents.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() > 3 && fields[0] == name {
+            let Some(uid) = fields[2].parse().ok() else {
+                continue;
+            };
+            let Some(gid) = fields[3].parse().ok() else {
+                continue;
+            };
+            return Ok(Some((uid, gid)));
+        }
+    }
+    Ok(None)
+}
+
+fn lookup_passwd_gid(rootfs: &Path, uid: u32) -> Result<Option<u32>> {
+    let contents = match std::fs::read_to_string(rootfs.join("etc/passwd")) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(None),
+    };
+
+    for line in contents.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() > 3 && fields[2].parse() == Ok(uid) {
+            return Ok(fields[3].parse().ok());
+        }
+    }
+    Ok(None)
+}
+
+fn lookup_group_gid(rootfs: &Path, name: &str) -> Result<Option<u32>> {
+    let contents = match std::fs::read_to_string(rootfs.join("etc/group")) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(None),
+    };
+
+    for line in contents.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() > 2 && fields[0] == name {
+            return Ok(fields[2].parse().ok());
+        }
+    }
+    Ok(None)
+}
+
+pub fn spec(
+    image_config: &ImageConfiguration,
+    extra_args: &[String],
+    extra_env: &[String],
+    seccomp_profile: Option<&Path>,
+    scheduler: Option<&SchedulerConfig>,
+    io_priority: Option<&IoPriorityConfig>,
+    bundle: &Path,
+) -> Result<()> {
     tracing::info!("Creating container spec");
-    let spec = get_rootless()?;
+    let mut spec = get_rootless(seccomp_profile, scheduler, io_priority)?;
+    apply_image_config(&mut spec, image_config, &bundle.join("rootfs"), extra_args, extra_env)?;
 
     // write data to config.json
-    let file = File::create("test/config.json")?;
+    let file = File::create(bundle.join("config.json"))?;
     let mut writer = BufWriter::new(file);
     to_writer_pretty(&mut writer, &spec)?;
     writer.flush()?;
@@ -139,28 +468,153 @@ async fn pull_image(image: &str) -> Result<ImageData, Box<dyn std::error::Error>
     Ok(client.pull(&reference, &auth, types).await?)
 }
 
+// Parse the image's config blob into an OCI ImageConfiguration.
+fn parse_image_config(image_data: &ImageData) -> Result<ImageConfiguration> {
+    Ok(serde_json::from_slice(&image_data.config.data)?)
+}
+
 #[tracing::instrument(skip(image_data))]
-async fn unpack_image(image_data: oci_distribution::client::ImageData) -> std::io::Result<()> {
+fn unpack_image(
+    image_data: oci_distribution::client::ImageData,
+    rootfs: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Unpacking image");
-    for layer in image_data.layers {
-        let tar_gz = Cursor::new(layer.data);
-        let tar = GzDecoder::new(tar_gz);
-        let mut archive = Archive::new(tar);
-        archive.unpack("test/rootfs")?;
+
+    // The manifest carries the expected digest for each layer, in the same order
+    // as `image_data.layers`.
+    let digests: Vec<String> = image_data
+        .manifest
+        .as_ref()
+        .map(|manifest| manifest.layers.iter().map(|l| l.digest.clone()).collect())
+        .unwrap_or_default();
+
+    // Layers must be applied in manifest order so that a later layer's whiteouts
+    // and overwrites win over an earlier layer's content.
+    for (index, layer) in image_data.layers.into_iter().enumerate() {
+        if let Some(digest) = digests.get(index) {
+            verify_layer_digest(&layer.data, digest)
+                .map_err(|err| format!("layer {index} failed integrity check: {err}"))?;
+        }
+        let reader = decode_layer(&layer.data, &layer.media_type)?;
+        unpack_layer(reader, rootfs)?;
     }
     Ok(())
 }
 
-fn run_container() -> Result<(), Box<dyn std::error::Error>> {
+// Verify a layer's raw bytes hash to the sha256:<hex> digest the manifest advertised.
+fn verify_layer_digest(data: &[u8], digest: &str) -> Result<(), String> {
+    let (algorithm, expected) = digest
+        .split_once(':')
+        .ok_or_else(|| format!("layer digest '{digest}' is missing an algorithm prefix"))?;
+    if algorithm != "sha256" {
+        return Err(format!("unsupported digest algorithm '{algorithm}'"));
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    let actual = format!("{:x}", hasher.finalize());
+    if actual != expected {
+        return Err(format!(
+            "digest mismatch: manifest says sha256:{expected}, layer hashes to sha256:{actual}"
+        ));
+    }
+    Ok(())
+}
+
+// Pick a decoder for a layer based on its advertised media type.
+// TODO: add a zstd branch once we advertise *.tar.zstd in pull_image.
+fn decode_layer<'a>(
+    data: &'a [u8],
+    media_type: &str,
+) -> Result<Box<dyn Read + 'a>, Box<dyn std::error::Error>> {
+    match media_type {
+        manifest::IMAGE_LAYER_GZIP_MEDIA_TYPE | manifest::IMAGE_DOCKER_LAYER_GZIP_MEDIA_TYPE => {
+            Ok(Box::new(GzDecoder::new(Cursor::new(data))))
+        }
+        manifest::IMAGE_LAYER_MEDIA_TYPE | manifest::IMAGE_DOCKER_LAYER_TAR_MEDIA_TYPE => {
+            Ok(Box::new(Cursor::new(data)))
+        }
+        other => Err(format!("unsupported layer media type '{other}'").into()),
+    }
+}
+
+// Extract a single layer's tar stream into `rootfs`, honoring the Docker/OCI
+// whiteout convention: a `.wh.<name>` entry deletes `<name>` instead of being
+// written, and `.wh..wh..opq` marks its directory opaque.
+fn unpack_layer<R: Read>(reader: R, rootfs: &Path) -> std::io::Result<()> {
+    let mut archive = Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let parent = path.parent().unwrap_or(Path::new(""));
+
+        if file_name == ".wh..wh..opq" {
+            remove_dir_children(&rootfs.join(parent))?;
+            continue;
+        }
+
+        if let Some(name) = file_name.strip_prefix(".wh.") {
+            remove_path(&rootfs.join(parent).join(name))?;
+            continue;
+        }
+
+        entry.unpack_in(rootfs)?;
+    }
+    Ok(())
+}
+
+// Delete every existing child of `dir`, used to implement opaque-directory whiteouts.
+fn remove_dir_children(dir: &Path) -> std::io::Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(ref err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    for entry in entries {
+        remove_path(&entry?.path())?;
+    }
+    Ok(())
+}
+
+// Remove a file, symlink, or directory (recursively) if it exists.
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(ref err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+fn run_container(tty: bool) -> Result<(), Box<dyn std::error::Error>> {
     let container_id = "my-container";
+    let console_socket_path = Path::new("test/console.sock");
+
+    let console_listener = if tty {
+        Some(bind_console_socket(console_socket_path)?)
+    } else {
+        None
+    };
+
     tracing::info!(container_id, "Creating container");
-    let mut container = ContainerBuilder::new(container_id.to_owned(), SyscallType::default())
+    let mut builder = ContainerBuilder::new(container_id.to_owned(), SyscallType::default())
         .with_executor(MyExecutor {})
         .with_pid_file(Some("test/container.pid"))
         .expect("invalid pid file")
-        // .with_console_socket(Some("/tmp/container/console.sock"))
         .with_root_path("test")
-        .expect("invalid root path")
+        .expect("invalid root path");
+
+    if tty {
+        builder = builder
+            .with_console_socket(Some(console_socket_path))
+            .expect("invalid console socket path");
+    }
+
+    let mut container = builder
         .validate_id()?
         .as_init("test")
         .with_systemd(false)
@@ -173,7 +627,22 @@ fn run_container() -> Result<(), Box<dyn std::error::Error>> {
         .start()
         .with_context(|| format!("failed to start container {}", container_id))?;
 
-    let _foreground_result = handle_foreground(container.pid().unwrap());
+    // Once the runtime has started, it connects back to the console socket and
+    // sends the pty master fd for the init process over SCM_RIGHTS.
+    let pty_master = console_listener
+        .map(|listener| recv_pty_master(&listener))
+        .transpose()?;
+
+    let _foreground_result = match pty_master {
+        Some(master) => {
+            let master_fd = master.as_raw_fd();
+            let proxy = std::thread::spawn(move || proxy_pty(master));
+            let result = handle_foreground(container.pid().unwrap(), Some(master_fd));
+            let _ = proxy.join();
+            result
+        }
+        None => handle_foreground(container.pid().unwrap(), None),
+    };
 
     tracing::info!(container_id, "Deleting container");
     container.delete(true)?;
@@ -181,8 +650,153 @@ fn run_container() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-#[tracing::instrument(level = "trace")]
-fn handle_foreground(init_pid: Pid) -> Result<i32> {
+// Bind the unix socket a container's --console-socket connects back to.
+fn bind_console_socket(path: &Path) -> Result<UnixListener> {
+    let _ = std::fs::remove_file(path);
+    UnixListener::bind(path)
+        .with_context(|| format!("failed to bind console socket {}", path.display()))
+}
+
+// Accept the runtime's connection and receive the pty master fd over SCM_RIGHTS.
+fn recv_pty_master(listener: &UnixListener) -> Result<OwnedFd> {
+    let (stream, _) = listener
+        .accept()
+        .context("failed to accept console socket connection")?;
+
+    let mut buf = [0u8; 4096];
+    let mut iov = [IoSliceMut::new(&mut buf)];
+    let mut cmsg_space = nix::cmsg_space!([RawFd; 1]);
+
+    let msg = recvmsg::<()>(
+        stream.as_raw_fd(),
+        &mut iov,
+        Some(&mut cmsg_space),
+        MsgFlags::empty(),
+    )
+    .context("failed to receive pty master fd over console socket")?;
+
+    for cmsg in msg.cmsgs()? {
+        if let ControlMessageOwned::ScmRights(fds) = cmsg {
+            if let Some(&fd) = fds.first() {
+                return Ok(unsafe { OwnedFd::from_raw_fd(fd) });
+            }
+        }
+    }
+
+    anyhow::bail!("console socket connection carried no pty master fd")
+}
+
+nix::ioctl_read_bad!(tiocgwinsz, nix::libc::TIOCGWINSZ, nix::libc::winsize);
+nix::ioctl_write_ptr_bad!(tiocswinsz, nix::libc::TIOCSWINSZ, nix::libc::winsize);
+
+// Read the host terminal's current size and apply it to the pty master.
+fn apply_host_window_size(master_fd: RawFd) -> Result<()> {
+    let mut winsize: nix::libc::winsize = unsafe { std::mem::zeroed() };
+    unsafe { tiocgwinsz(std::io::stdin().as_raw_fd(), &mut winsize) }
+        .context("failed to read host terminal size")?;
+    unsafe { tiocswinsz(master_fd, &winsize) }
+        .context("failed to apply terminal size to pty master")?;
+    Ok(())
+}
+
+// Put the host terminal in raw mode and proxy stdin/stdout to/from the pty
+// master, restoring the original terminal settings on exit.
+fn proxy_pty(master: OwnedFd) -> Result<()> {
+    let master_in = unsafe { File::from_raw_fd(nix::unistd::dup(master.as_raw_fd())?) };
+    let stdin_fd = std::io::stdin().as_raw_fd();
+    let original_termios = termios::tcgetattr(stdin_fd).ok();
+    if let Some(termios) = &original_termios {
+        let mut raw = termios.clone();
+        termios::cfmakeraw(&mut raw);
+        termios::tcsetattr(stdin_fd, SetArg::TCSANOW, &raw)?;
+    }
+
+    if let Err(err) = apply_host_window_size(master.as_raw_fd()) {
+        tracing::warn!(?err, "failed to set initial pty size");
+    }
+
+    let mut master_out = unsafe { File::from_raw_fd(master.into_raw_fd()) };
+
+    let reader = std::thread::spawn(move || {
+        let mut stdin = std::io::stdin();
+        let mut master_in = master_in;
+        let _ = std::io::copy(&mut stdin, &mut master_in);
+    });
+
+    let copy_result = std::io::copy(&mut master_out, &mut std::io::stdout());
+
+    // The reader thread is blocked on a read of the host's stdin and won't wake
+    // up until the user sends EOF or closes the terminal, which may be long
+    // after the container's shell has already exited; abandon it instead of
+    // joining so an interactive run can clean up as soon as the copy above ends.
+    drop(reader);
+
+    if let Some(termios) = original_termios {
+        let _ = termios::tcsetattr(stdin_fd, SetArg::TCSANOW, &termios);
+    }
+
+    copy_result.map(|_| ()).context("pty proxy I/O failed")
+}
+
+// Build and start an additional process inside an already-running container,
+// the `runc exec`/`docker exec` equivalent, returning its pid. Joins the init
+// process's namespaces and cgroup via the tenant builder rather than creating
+// a new container.
+fn start_exec_container(
+    container_id: &str,
+    root: &Path,
+    command: Vec<String>,
+    env: Vec<String>,
+    cwd: Option<PathBuf>,
+) -> Result<Pid, Box<dyn std::error::Error>> {
+    tracing::info!(container_id, "Executing process in running container");
+
+    let env_map: HashMap<String, String> = env
+        .into_iter()
+        .filter_map(|kv| kv.split_once('=').map(|(k, v)| (k.to_string(), v.to_string())))
+        .collect();
+
+    // `as_tenant` takes the cgroup path from the existing container's state and
+    // never sets up a user namespace of its own; it only joins the namespaces
+    // the init process already created.
+    let mut tenant_builder = ContainerBuilder::new(container_id.to_owned(), SyscallType::default())
+        .with_root_path(root)
+        .expect("invalid root path")
+        .as_tenant()
+        .with_container_args(command)
+        .with_env(env_map);
+
+    if let Some(cwd) = cwd {
+        tenant_builder = tenant_builder.with_cwd(Some(cwd));
+    }
+
+    let mut tenant_container = tenant_builder.build()?;
+
+    tracing::info!(container_id, "Starting tenant process");
+    tenant_container
+        .start()
+        .with_context(|| format!("failed to start exec process in container {}", container_id))?;
+
+    Ok(tenant_container.pid().unwrap())
+}
+
+// CLI-facing exec: start the tenant process and block this thread until it
+// exits. The daemon uses `start_exec_container` plus the shared `Reaper`
+// instead, so concurrent execs/starts don't race each other in `waitpid`.
+fn exec_container(
+    container_id: &str,
+    root: &Path,
+    command: Vec<String>,
+    env: Vec<String>,
+    cwd: Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pid = start_exec_container(container_id, root, command, env, cwd)?;
+    let _foreground_result = handle_foreground(pid, None);
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(pty_master))]
+fn handle_foreground(init_pid: Pid, pty_master: Option<RawFd>) -> Result<i32> {
     tracing::trace!("waiting for container init process to exit");
     // We mask all signals here and forward most of the signals to the container
     // init process.
@@ -229,7 +843,11 @@ fn handle_foreground(init_pid: Pid) -> Result<i32> {
                 // the container process. Here, we just ignore the signal.
             }
             signal::SIGWINCH => {
-                // TODO: resize the terminal
+                if let Some(master_fd) = pty_master {
+                    if let Err(err) = apply_host_window_size(master_fd) {
+                        tracing::warn!(?err, "failed to resize container terminal");
+                    }
+                }
             }
             signal => {
                 tracing::trace!(?signal, "forwarding signal");
@@ -246,6 +864,395 @@ fn handle_foreground(init_pid: Pid) -> Result<i32> {
     }
 }
 
+// Each container gets its own bundle directory (config.json + rootfs) under
+// test/, keyed by container id, so one container's pull/create can't clobber
+// another's spec or filesystem.
+fn bundle_dir(container_id: &str) -> PathBuf {
+    Path::new("test").join(container_id)
+}
+
+// One dedicated thread owns the `sigwait`/`waitpid(None)` loop for every
+// container the daemon manages and dispatches each exit to whoever is
+// waiting on that pid. `waitpid(None, ...)` reaps whichever child is ripe
+// first regardless of which thread called it, so more than one thread
+// blocked in it at once (as plain `handle_foreground` would be, one per
+// container) can reap the wrong container's exit out from under its waiter.
+struct Reaper {
+    state: Mutex<ReaperState>,
+}
+
+// How long an unclaimed exit status is kept in `ReaperState::pending` before
+// it's pruned. Entries claimed by a `wait_for` (including stale ones from a
+// reused pid, see its `spawned_at` check) are removed immediately; this TTL
+// only bounds pids that are never waited on at all (e.g. the runtime's own
+// helper processes), which would otherwise sit in `pending` forever.
+const PENDING_EXIT_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Default)]
+struct ReaperState {
+    waiters: HashMap<Pid, std::sync::mpsc::Sender<i32>>,
+    // Exits reaped before `wait_for` registered a waiter for that pid (the
+    // child can exit and be reaped between `start()`/`start_exec_container`
+    // returning a pid and the caller calling `wait_for` with it); held here
+    // so that race can't turn into a lost wakeup.
+    pending: HashMap<Pid, (i32, std::time::Instant)>,
+}
+
+impl Reaper {
+    fn spawn() -> Arc<Self> {
+        let reaper = Arc::new(Self {
+            state: Mutex::new(ReaperState::default()),
+        });
+        let background = reaper.clone();
+        std::thread::spawn(move || background.run());
+        reaper
+    }
+
+    fn run(&self) {
+        let signal_set = SigSet::all();
+        if signal_set.thread_block().is_err() {
+            return;
+        }
+        loop {
+            match signal_set.wait() {
+                Ok(signal::SIGCHLD) => self.reap_all(),
+                Ok(_) => {}
+                Err(_) => return,
+            }
+        }
+    }
+
+    fn reap_all(&self) {
+        loop {
+            match waitpid(None, Some(WaitPidFlag::WNOHANG)) {
+                Ok(WaitStatus::Exited(pid, status)) => self.notify(pid, status),
+                Ok(WaitStatus::Signaled(pid, signal, _)) => self.notify(pid, signal as i32),
+                Ok(WaitStatus::StillAlive) | Err(_) => break,
+                _ => {}
+            }
+        }
+        self.state
+            .lock()
+            .unwrap()
+            .pending
+            .retain(|_, (_, reaped_at)| reaped_at.elapsed() < PENDING_EXIT_TTL);
+    }
+
+    fn notify(&self, pid: Pid, status: i32) {
+        let mut state = self.state.lock().unwrap();
+        match state.waiters.remove(&pid) {
+            Some(tx) => {
+                let _ = tx.send(status);
+            }
+            None => {
+                state.pending.insert(pid, (status, std::time::Instant::now()));
+            }
+        }
+    }
+
+    // Block the calling (blocking) thread until `pid` exits. `spawned_at` must
+    // be captured before the process was started: a `pending` entry older
+    // than that can only be a stale status left behind by a previous process
+    // that held this pid before the kernel reused it (that process can't have
+    // been forked, let alone reaped, before `spawned_at`), so it's discarded
+    // rather than handed back as this process's exit status.
+    fn wait_for(&self, pid: Pid, spawned_at: std::time::Instant) -> Result<i32> {
+        let rx = {
+            let mut state = self.state.lock().unwrap();
+            if let Some((status, reaped_at)) = state.pending.remove(&pid) {
+                if reaped_at >= spawned_at {
+                    return Ok(status);
+                }
+            }
+            let (tx, rx) = std::sync::mpsc::channel();
+            state.waiters.insert(pid, tx);
+            rx
+        };
+        rx.recv()
+            .context("reaper thread exited before reporting this pid's exit status")
+    }
+}
+
+// State the daemon keeps for a container it has created, beyond what
+// `libcontainer` itself persists on disk under the container's bundle dir.
+struct ManagedContainer {
+    container: Container,
+    pid: Option<Pid>,
+    // Taken before `create` forks the init process; `start` only releases it.
+    created_at: std::time::Instant,
+}
+
+// The reusable pull/create/start/exec/signal/delete engine behind the daemon's
+// control protocol. Containers are tracked in a map keyed by container id, and
+// child exits are all waited for through the shared `Reaper` rather than each
+// container running its own sigwait loop. The one-shot CLI flow in `main` does
+// not go through it; it calls `pull_image`, `unpack_image`, `spec` and
+// `run_container` directly.
+struct Engine {
+    containers: Mutex<HashMap<String, ManagedContainer>>,
+    reaper: Arc<Reaper>,
+}
+
+impl Engine {
+    fn new() -> Self {
+        Self {
+            containers: Mutex::new(HashMap::new()),
+            reaper: Reaper::spawn(),
+        }
+    }
+
+    async fn pull(&self, id: &str, image: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if self.containers.lock().unwrap().contains_key(id) {
+            return Err(format!("container '{id}' already exists").into());
+        }
+        let bundle = bundle_dir(id);
+        std::fs::create_dir_all(&bundle)?;
+        let image_data = pull_image(image).await?;
+        let image_config = parse_image_config(&image_data)?;
+
+        // Unpacking a layer set is synchronous tar/gzip decoding and disk I/O with
+        // no real await point, so run it (and building the spec) on a blocking
+        // task rather than tying up a tokio worker thread for the whole pull.
+        let result = tokio::task::spawn_blocking(move || {
+            unpack_image(image_data, &bundle.join("rootfs")).map_err(|err| err.to_string())?;
+            spec(&image_config, &[], &[], None, None, None, &bundle).map_err(|err| err.to_string())
+        })
+        .await
+        .map_err(|err| format!("pull task panicked: {err}"))?;
+
+        result.map_err(|err| err.into())
+    }
+
+    fn create(&self, container_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if self.containers.lock().unwrap().contains_key(container_id) {
+            return Err(format!("container '{container_id}' already exists").into());
+        }
+        let bundle = bundle_dir(container_id);
+        let created_at = std::time::Instant::now();
+        let container = ContainerBuilder::new(container_id.to_owned(), SyscallType::default())
+            .with_executor(MyExecutor {})
+            .with_pid_file(Some(bundle.join("container.pid")))
+            .expect("invalid pid file")
+            .with_root_path(bundle.clone())
+            .expect("invalid root path")
+            .validate_id()?
+            .as_init(bundle.clone())
+            .with_systemd(false)
+            .with_detach(true)
+            .build()?;
+
+        self.containers.lock().unwrap().insert(
+            container_id.to_owned(),
+            ManagedContainer {
+                container,
+                pid: None,
+                created_at,
+            },
+        );
+        Ok(())
+    }
+
+    fn start(&self, container_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut containers = self.containers.lock().unwrap();
+        let managed = containers
+            .get_mut(container_id)
+            .ok_or_else(|| format!("unknown container '{container_id}'"))?;
+
+        let spawned_at = managed.created_at;
+        managed
+            .container
+            .start()
+            .with_context(|| format!("failed to start container {container_id}"))?;
+        managed.pid = managed.container.pid();
+
+        // Wait for this container's exit on a blocking task so the control
+        // server's request loop is never blocked, but route the actual wait
+        // through the shared reaper rather than running our own sigwait loop.
+        if let Some(pid) = managed.pid {
+            let reaper = self.reaper.clone();
+            tokio::task::spawn_blocking(move || {
+                let _ = reaper.wait_for(pid, spawned_at);
+            });
+        }
+        Ok(())
+    }
+
+    async fn exec(
+        &self,
+        container_id: &str,
+        command: Vec<String>,
+        env: Vec<String>,
+        cwd: Option<PathBuf>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.containers.lock().unwrap().contains_key(container_id) {
+            return Err(format!("unknown container '{container_id}'").into());
+        }
+        let bundle = bundle_dir(container_id);
+        let container_id = container_id.to_owned();
+        let reaper = self.reaper.clone();
+
+        // `start_exec_container` blocks while the tenant process starts, and
+        // the wait below blocks until it exits; run both on a blocking task so
+        // a long-running exec can't starve the control server's async request
+        // loop the way a bare `await`-less call here would.
+        let result = tokio::task::spawn_blocking(move || {
+            let spawned_at = std::time::Instant::now();
+            let pid = start_exec_container(&container_id, &bundle, command, env, cwd)
+                .map_err(|err| err.to_string())?;
+            reaper
+                .wait_for(pid, spawned_at)
+                .map_err(|err| err.to_string())
+        })
+        .await
+        .map_err(|err| format!("exec task panicked: {err}"))?;
+
+        result.map(|_| ()).map_err(|err| err.into())
+    }
+
+    fn signal(&self, container_id: &str, signal: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let containers = self.containers.lock().unwrap();
+        let managed = containers
+            .get(container_id)
+            .ok_or_else(|| format!("unknown container '{container_id}'"))?;
+        let pid = managed
+            .pid
+            .ok_or("container has not been started")?;
+        let signal = signal::Signal::from_str(signal)
+            .map_err(|_| format!("unknown signal '{signal}'"))?;
+        kill(pid, Some(signal))?;
+        Ok(())
+    }
+
+    fn delete(&self, container_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut managed = self
+            .containers
+            .lock()
+            .unwrap()
+            .remove(container_id)
+            .ok_or_else(|| format!("unknown container '{container_id}'"))?;
+        managed.container.delete(true)?;
+        Ok(())
+    }
+}
+
+// The daemon's line/JSON control protocol: one verb per line in, one DaemonResponse out.
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "verb", rename_all = "lowercase")]
+enum DaemonRequest {
+    Pull {
+        id: String,
+        image: String,
+    },
+    Create {
+        id: String,
+    },
+    Start {
+        id: String,
+    },
+    Exec {
+        id: String,
+        command: Vec<String>,
+        #[serde(default)]
+        env: Vec<String>,
+        #[serde(default)]
+        cwd: Option<PathBuf>,
+    },
+    Signal {
+        id: String,
+        signal: String,
+    },
+    Delete {
+        id: String,
+    },
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DaemonResponse {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DaemonResponse {
+    fn from_result(result: Result<(), Box<dyn std::error::Error>>) -> Self {
+        match result {
+            Ok(()) => DaemonResponse {
+                ok: true,
+                error: None,
+            },
+            Err(err) => DaemonResponse {
+                ok: false,
+                error: Some(err.to_string()),
+            },
+        }
+    }
+}
+
+async fn dispatch_request(engine: &Engine, request: DaemonRequest) -> DaemonResponse {
+    let result = match request {
+        DaemonRequest::Pull { id, image } => engine.pull(&id, &image).await,
+        DaemonRequest::Create { id } => engine.create(&id),
+        DaemonRequest::Start { id } => engine.start(&id),
+        DaemonRequest::Exec {
+            id,
+            command,
+            env,
+            cwd,
+        } => engine.exec(&id, command, env, cwd).await,
+        DaemonRequest::Signal { id, signal } => engine.signal(&id, &signal),
+        DaemonRequest::Delete { id } => engine.delete(&id),
+    };
+    DaemonResponse::from_result(result)
+}
+
+async fn handle_control_connection(
+    stream: tokio::net::UnixStream,
+    engine: Arc<Engine>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = tokio::io::BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<DaemonRequest>(&line) {
+            Ok(request) => dispatch_request(&engine, request).await,
+            Err(err) => DaemonResponse {
+                ok: false,
+                error: Some(format!("invalid request: {err}")),
+            },
+        };
+
+        let mut payload = serde_json::to_vec(&response)?;
+        payload.push(b'\n');
+        writer.write_all(&payload).await?;
+    }
+    Ok(())
+}
+
+// Listen on `socket_path` for line-delimited JSON control requests, sharing one
+// `Engine` across connections so a container created over one connection can
+// be started, exec'd into, signaled, or deleted from another.
+async fn run_daemon(socket_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let _ = std::fs::remove_file(socket_path);
+    let listener = tokio::net::UnixListener::bind(socket_path)
+        .with_context(|| format!("failed to bind control socket {}", socket_path.display()))?;
+    let engine = Arc::new(Engine::new());
+
+    tracing::info!(socket = %socket_path.display(), "control server listening");
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let engine = engine.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_control_connection(stream, engine).await {
+                tracing::warn!(?err, "control connection failed");
+            }
+        });
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::registry()
@@ -253,9 +1260,112 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let mut args = std::env::args().skip(1).peekable();
+
+    // `oci-test daemon [socket-path]` starts the control server instead of
+    // running the default one-shot flow; defaults to `test/control.sock`.
+    if args.peek().map(String::as_str) == Some("daemon") {
+        args.next();
+        let socket_path = args
+            .next()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("test/control.sock"));
+        return run_daemon(&socket_path).await;
+    }
+
+    // `oci-test exec <container-id> <root> [--env K=V]... [--cwd DIR] -- <command...>`
+    // runs a process inside an already-running container instead of the default
+    // pull -> unpack -> spec -> run flow.
+    if args.peek().map(String::as_str) == Some("exec") {
+        args.next();
+        let container_id = args.next().context("exec requires a container id")?;
+        let root = args.next().context("exec requires a root path")?;
+        let mut env = Vec::new();
+        let mut cwd = None;
+        let mut command = Vec::new();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--env" => env.push(args.next().context("--env requires a value")?),
+                "--cwd" => cwd = Some(PathBuf::from(args.next().context("--cwd requires a value")?)),
+                "--" => command.extend(args.by_ref()),
+                other => command.push(other.to_string()),
+            }
+        }
+        return exec_container(&container_id, Path::new(&root), command, env, cwd);
+    }
+
+    // CLI overrides appended on top of the image's own Entrypoint/Cmd/Env.
+    let mut extra_args = Vec::new();
+    let mut extra_env = Vec::new();
+    let mut seccomp_profile: Option<PathBuf> = None;
+    let mut tty = false;
+    let mut scheduler = SchedulerConfig::default();
+    let mut has_scheduler = false;
+    let mut io_priority = IoPriorityConfig::default();
+    let mut has_io_priority = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--arg" => extra_args.push(args.next().context("--arg requires a value")?),
+            "--env" => extra_env.push(args.next().context("--env requires a value")?),
+            "--seccomp-profile" => {
+                seccomp_profile = Some(PathBuf::from(
+                    args.next().context("--seccomp-profile requires a value")?,
+                ))
+            }
+            "--tty" => tty = true,
+            "--sched-policy" => {
+                scheduler.policy = args.next().context("--sched-policy requires a value")?;
+                has_scheduler = true;
+            }
+            "--sched-nice" => {
+                scheduler.nice = Some(
+                    args.next()
+                        .context("--sched-nice requires a value")?
+                        .parse()
+                        .context("--sched-nice must be an integer")?,
+                );
+                has_scheduler = true;
+            }
+            "--sched-flag" => {
+                scheduler
+                    .flags
+                    .push(args.next().context("--sched-flag requires a value")?);
+                has_scheduler = true;
+            }
+            "--io-class" => {
+                io_priority.class = args.next().context("--io-class requires a value")?;
+                has_io_priority = true;
+            }
+            "--io-priority" => {
+                let priority: i64 = args
+                    .next()
+                    .context("--io-priority requires a value")?
+                    .parse()
+                    .context("--io-priority must be an integer between 0 and 7")?;
+                anyhow::ensure!(
+                    (0..=7).contains(&priority),
+                    "--io-priority must be an integer between 0 and 7, got {priority}"
+                );
+                io_priority.priority = priority;
+                has_io_priority = true;
+            }
+            other => tracing::warn!(arg = other, "ignoring unrecognized argument"),
+        }
+    }
+
+    let bundle = Path::new("test");
     let image_data = pull_image("docker.io/library/alpine:latest").await?;
-    unpack_image(image_data).await?;
-    spec()?;
-    run_container()?;
+    let image_config = parse_image_config(&image_data)?;
+    unpack_image(image_data, &bundle.join("rootfs"))?;
+    spec(
+        &image_config,
+        &extra_args,
+        &extra_env,
+        seccomp_profile.as_deref(),
+        has_scheduler.then_some(&scheduler),
+        has_io_priority.then_some(&io_priority),
+        bundle,
+    )?;
+    run_container(tty)?;
     Ok(())
 }